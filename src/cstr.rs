@@ -0,0 +1,110 @@
+/// Creates a `&'static `[`CStr`](std::ffi::CStr) from a string literal.
+///
+/// # Examples
+///
+/// This macro takes either a `"string"` literal or `const` string slice as
+/// the argument:
+///
+/// ```
+/// let hello = fruity::cstr!("hello");
+/// assert_eq!(hello.to_bytes(), b"hello");
+///
+/// const WORLD: &str = "world";
+/// let world = fruity::cstr!(WORLD);
+/// assert_eq!(world.to_bytes(), b"world");
+/// ```
+///
+/// The result of this macro can even be used to initialize `static` values:
+///
+/// ```
+/// # use std::ffi::CStr;
+/// static WORLD: &CStr = fruity::cstr!("world");
+///
+/// assert_eq!(WORLD.to_bytes(), b"world");
+/// ```
+///
+/// # Null-Terminated Strings
+///
+/// If the input string already ends with a 0 byte, then this macro does not
+/// append one.
+///
+/// ```
+/// let cstr = fruity::cstr!("example\0");
+/// let normal = fruity::cstr!("example");
+///
+/// assert_eq!(cstr, normal);
+/// ```
+///
+/// Interior null bytes are rejected at compile time:
+///
+/// ```compile_fail
+/// let bad = fruity::cstr!("exa\0mple");
+/// ```
+///
+/// # Runtime Cost
+///
+/// None.
+///
+/// Because of that, this should be preferred over allocating a `CString`
+/// where possible.
+///
+/// # Compile-time Cost
+///
+/// Minimal.
+///
+/// This is implemented entirely with `const` evaluation, mirroring
+/// [`nsstring!`](crate::nsstring!). It is not a procedural macro that
+/// requires dependencies for parsing.
+#[macro_export]
+macro_rules! cstr {
+    ($s:expr) => {{
+        // As in `nsstring!`, only full `$crate`-qualified paths are used here
+        // so that this macro does not import any names that could shadow
+        // those at the call site.
+
+        // Remove any trailing null early so that exactly one can be appended
+        // below, regardless of whether the caller's literal already has one.
+        const INPUT: &[$crate::_priv::std::primitive::u8] =
+            $crate::_priv::cfstring::trim_trailing_nul($s);
+
+        const fn check_no_interior_nul(bytes: &[$crate::_priv::std::primitive::u8]) {
+            let mut i = 0;
+            while i < bytes.len() {
+                if bytes[i] == 0 {
+                    panic!("cstr! argument contains an interior NUL byte");
+                }
+                i += 1;
+            }
+        }
+        // Binding the call to a `const` (rather than calling it as a plain
+        // statement) forces it through CTFE, so an interior NUL byte is a
+        // compile error rather than a runtime panic.
+        const _CHECK: () = check_no_interior_nul(INPUT);
+
+        // The input bytes with a single trailing null byte appended.
+        #[repr(C)]
+        struct Data {
+            bytes: [$crate::_priv::std::primitive::u8; INPUT.len()],
+            nul: $crate::_priv::std::primitive::u8,
+        }
+
+        const DATA: Data = Data {
+            bytes: unsafe { *$crate::_priv::std::mem::transmute::<_, &_>(INPUT.as_ptr()) },
+            nul: 0,
+        };
+
+        const ARRAY: &[$crate::_priv::std::primitive::u8; INPUT.len() + 1] =
+            unsafe { $crate::_priv::std::mem::transmute(&DATA) };
+
+        #[link_section = "__TEXT,__cstring"]
+        static BYTES: [$crate::_priv::std::primitive::u8; INPUT.len() + 1] = *ARRAY;
+
+        const CSTR: &'static $crate::_priv::std::ffi::CStr =
+            match $crate::_priv::std::ffi::CStr::from_bytes_with_nul(&BYTES) {
+                Ok(cstr) => cstr,
+                Err(_) => panic!("cstr! produced a malformed C string"),
+            };
+
+        CSTR
+    }};
+}
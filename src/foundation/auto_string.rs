@@ -0,0 +1,233 @@
+//! A stack-friendly `NSString` builder, analogous to XPCOM's
+//! `nsFixedString` / `ns_auto_string!`.
+
+use std::fmt;
+
+use super::{ffi, NSString};
+
+/// Short strings are assembled in this many `u16` code units before
+/// spilling onto the heap.
+const INLINE_CAPACITY: usize = 32;
+
+enum Buf {
+    Inline { data: [u16; INLINE_CAPACITY], len: usize },
+    Heap(Vec<u16>),
+}
+
+/// A mutable string builder that assembles UTF-16 fragments in place and
+/// only talks to Core Foundation once, when [`finish`](AutoNSString::finish)
+/// materializes the result.
+///
+/// Short content is assembled in an inline buffer with no heap allocation;
+/// longer content spills onto a `Vec<u16>`.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::Write as _;
+/// use fruity::foundation::AutoNSString;
+///
+/// let mut s = AutoNSString::new();
+/// s.push_str("count: ");
+/// write!(s, "{}", 42).unwrap();
+///
+/// let nsstring = s.finish();
+/// assert_eq!(nsstring.to_string(), "count: 42");
+/// ```
+pub struct AutoNSString {
+    buf: Buf,
+}
+
+impl AutoNSString {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        AutoNSString {
+            buf: Buf::Inline {
+                data: [0u16; INLINE_CAPACITY],
+                len: 0,
+            },
+        }
+    }
+
+    /// Appends a UTF-16 code unit, spilling onto the heap first if the
+    /// inline buffer is full.
+    fn push_utf16(&mut self, unit: u16) {
+        match &mut self.buf {
+            Buf::Inline { data, len } if *len < INLINE_CAPACITY => {
+                data[*len] = unit;
+                *len += 1;
+            }
+            Buf::Inline { data, len } => {
+                let mut heap = Vec::with_capacity(INLINE_CAPACITY * 2);
+                heap.extend_from_slice(&data[..*len]);
+                heap.push(unit);
+                self.buf = Buf::Heap(heap);
+            }
+            Buf::Heap(heap) => heap.push(unit),
+        }
+    }
+
+    /// Appends the contents of `s`.
+    pub fn push_str(&mut self, s: &str) {
+        let mut units = [0u16; 2];
+        for c in s.chars() {
+            for unit in c.encode_utf16(&mut units) {
+                self.push_utf16(*unit);
+            }
+        }
+    }
+
+    /// Appends a single character.
+    pub fn push(&mut self, c: char) {
+        let mut units = [0u16; 2];
+        for unit in c.encode_utf16(&mut units) {
+            self.push_utf16(*unit);
+        }
+    }
+
+    /// Consumes the builder and materializes an [`NSString`] from its
+    /// contents.
+    ///
+    /// This performs exactly one Core Foundation call,
+    /// `CFStringCreateWithCharactersNoCopy`, which takes ownership of a heap
+    /// buffer holding the contents. Inline contents are copied onto the heap
+    /// for this; spilled contents are moved into a boxed slice, which may
+    /// reallocate to drop spare capacity.
+    pub fn finish(self) -> NSString<'static> {
+        // `CFStringCreateWithCharactersNoCopy` needs a buffer Core
+        // Foundation can free with `free()` once it is done with it, so the
+        // contents are moved onto the heap (if they are not already there)
+        // and leaked to CF's care.
+        let heap = match self.buf {
+            Buf::Inline { data, len } => data[..len].to_vec(),
+            Buf::Heap(heap) => heap,
+        };
+
+        if heap.is_empty() {
+            // `Vec::new().into_boxed_slice()` never actually allocates, so
+            // there is no heap pointer here for CF to take ownership of and
+            // later `free()`. Hand back a dangling, zero-length pointer with
+            // `kCFAllocatorNull` instead, which tells CF the pointer is
+            // borrowed rather than something to free.
+            return unsafe {
+                let cf_ref = ffi::CFStringCreateWithCharactersNoCopy(
+                    ffi::kCFAllocatorDefault,
+                    std::ptr::NonNull::dangling().as_ptr(),
+                    0,
+                    ffi::kCFAllocatorNull,
+                );
+                NSString::from_ptr(cf_ref)
+            };
+        }
+
+        let boxed = heap.into_boxed_slice();
+        let len = boxed.len();
+        let ptr = Box::into_raw(boxed) as *mut u16;
+
+        unsafe {
+            let cf_ref = ffi::CFStringCreateWithCharactersNoCopy(
+                ffi::kCFAllocatorDefault,
+                ptr,
+                len as ffi::CFIndex,
+                ffi::kCFAllocatorDefault,
+            );
+            NSString::from_ptr(cf_ref)
+        }
+    }
+}
+
+impl Default for AutoNSString {
+    fn default() -> Self {
+        AutoNSString::new()
+    }
+}
+
+impl fmt::Write for AutoNSString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+/// A piece that can be appended to an [`AutoNSString`] by
+/// [`ns_auto_string!`](crate::ns_auto_string!).
+pub trait AutoNSStringPiece {
+    /// Appends `self` to `into`.
+    fn push_into(self, into: &mut AutoNSString);
+}
+
+impl AutoNSStringPiece for &str {
+    fn push_into(self, into: &mut AutoNSString) {
+        into.push_str(self);
+    }
+}
+
+impl AutoNSStringPiece for char {
+    fn push_into(self, into: &mut AutoNSString) {
+        into.push(self);
+    }
+}
+
+impl AutoNSStringPiece for u16 {
+    fn push_into(self, into: &mut AutoNSString) {
+        into.push_utf16(self);
+    }
+}
+
+/// Declares a local [`AutoNSString`] and appends each given piece
+/// (`&str`, `char`, or `u16`) to it in order.
+///
+/// # Feature Flag
+///
+/// This macro is defined in [`foundation`](crate::foundation), which
+/// requires the **`foundation`** [feature flag](index.html#feature-flags).
+///
+/// # Examples
+///
+/// ```
+/// fruity::ns_auto_string!(greeting, "hello, ", "world", '!');
+/// assert_eq!(greeting.finish().to_string(), "hello, world!");
+/// ```
+#[macro_export]
+macro_rules! ns_auto_string {
+    ($name:ident $(, $piece:expr)* $(,)?) => {
+        let mut $name = $crate::foundation::AutoNSString::new();
+        $( $crate::foundation::AutoNSStringPiece::push_into($piece, &mut $name); )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AutoNSString;
+    use std::fmt::Write as _;
+
+    #[test]
+    fn finish_on_empty_builder() {
+        let s = AutoNSString::new();
+        assert_eq!(s.finish().to_string(), "");
+    }
+
+    #[test]
+    fn finish_spills_past_inline_capacity() {
+        let long: String = "x".repeat(super::INLINE_CAPACITY * 2);
+
+        let mut s = AutoNSString::new();
+        s.push_str(&long);
+
+        assert_eq!(s.finish().to_string(), long);
+    }
+
+    #[test]
+    fn finish_after_write_fmt() {
+        let mut s = AutoNSString::new();
+        s.push_str("count: ");
+        write!(s, "{}", 42).unwrap();
+
+        assert_eq!(s.finish().to_string(), "count: 42");
+    }
+}
@@ -0,0 +1,350 @@
+//! Bindings to the `Foundation` framework.
+//!
+//! # Feature Flag
+//!
+//! This module requires the **`foundation`** [feature
+//! flag](index.html#feature-flags).
+
+use std::borrow::Cow;
+use std::ffi::c_void;
+use std::fmt;
+use std::marker::PhantomData;
+
+mod auto_string;
+mod nsstring;
+
+pub use auto_string::{AutoNSString, AutoNSStringPiece};
+
+#[allow(non_upper_case_globals, dead_code)]
+mod ffi {
+    use std::ffi::c_void;
+    use std::os::raw::c_char;
+
+    pub type CFIndex = isize;
+    pub type CFStringRef = *const c_void;
+    pub type CFAllocatorRef = *const c_void;
+    pub type CFStringEncoding = u32;
+
+    #[repr(C)]
+    pub struct CFRange {
+        pub location: CFIndex,
+        pub length: CFIndex,
+    }
+
+    pub const kCFStringEncodingUTF8: CFStringEncoding = 0x0800_0100;
+    pub const kCFStringEncodingUTF16: CFStringEncoding = 0x0100_0100;
+    pub const kCFStringEncodingISOLatin1: CFStringEncoding = 0x0201;
+
+    extern "C" {
+        pub static kCFAllocatorDefault: CFAllocatorRef;
+        pub static kCFAllocatorNull: CFAllocatorRef;
+
+        pub fn CFStringGetLength(the_string: CFStringRef) -> CFIndex;
+        pub fn CFStringGetCStringPtr(
+            the_string: CFStringRef,
+            encoding: CFStringEncoding,
+        ) -> *const c_char;
+        pub fn CFStringGetCharacters(the_string: CFStringRef, range: CFRange, buffer: *mut u16);
+        pub fn CFStringGetBytes(
+            the_string: CFStringRef,
+            range: CFRange,
+            encoding: CFStringEncoding,
+            loss_byte: u8,
+            is_external_representation: u8,
+            buffer: *mut u8,
+            max_buf_len: CFIndex,
+            used_buf_len: *mut CFIndex,
+        ) -> CFIndex;
+        pub fn CFStringCreateWithBytes(
+            alloc: CFAllocatorRef,
+            bytes: *const u8,
+            num_bytes: CFIndex,
+            encoding: CFStringEncoding,
+            is_external_representation: u8,
+        ) -> CFStringRef;
+        pub fn CFStringCreateWithCharactersNoCopy(
+            alloc: CFAllocatorRef,
+            chars: *const u16,
+            num_chars: CFIndex,
+            contents_deallocator: CFAllocatorRef,
+        ) -> CFStringRef;
+        pub fn CFStringCreateWithBytesNoCopy(
+            alloc: CFAllocatorRef,
+            bytes: *const u8,
+            num_bytes: CFIndex,
+            encoding: CFStringEncoding,
+            is_external_representation: u8,
+            contents_deallocator: CFAllocatorRef,
+        ) -> CFStringRef;
+    }
+}
+
+/// An immutable Unicode string, toll-free bridged with `CFString`.
+///
+/// # Feature Flag
+///
+/// This is defined in [`foundation`](index.html), which requires the
+/// **`foundation`** [feature flag](index.html#feature-flags).
+#[repr(transparent)]
+pub struct NSString<'a> {
+    ptr: *mut c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+unsafe impl Send for NSString<'_> {}
+unsafe impl Sync for NSString<'_> {}
+
+impl<'a> NSString<'a> {
+    /// Wraps a raw, already-retained `NSString *` (or toll-free bridged
+    /// `CFStringRef`) pointer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must refer to a valid `NSString` instance that stays alive for
+    /// at least `'a`.
+    pub const unsafe fn from_ptr(ptr: *const c_void) -> Self {
+        NSString {
+            ptr: ptr as *mut c_void,
+            _marker: PhantomData,
+        }
+    }
+
+    fn as_cf_ref(&self) -> ffi::CFStringRef {
+        self.ptr as ffi::CFStringRef
+    }
+
+    /// Creates a new `NSString` by copying the contents of `s`.
+    ///
+    /// Prefer [`nsstring!`](crate::nsstring!) over this when `s` is known at
+    /// compile time, since that macro avoids the allocation and copy this
+    /// performs.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> NSString<'static> {
+        unsafe {
+            let cf_ref = ffi::CFStringCreateWithBytes(
+                ffi::kCFAllocatorDefault,
+                s.as_ptr(),
+                s.len() as ffi::CFIndex,
+                ffi::kCFStringEncodingUTF8,
+                0,
+            );
+            NSString::from_ptr(cf_ref)
+        }
+    }
+
+    /// Creates an `NSString` that wraps `buf` directly, without copying or
+    /// transcoding.
+    ///
+    /// This is the runtime analog of [`nsstring!`](crate::nsstring!) for
+    /// UTF-16 data the caller already has in memory: Core Foundation is told
+    /// (via `kCFAllocatorNull` as the contents deallocator) that it does not
+    /// own `buf`, so it neither copies nor frees it.
+    ///
+    /// The returned `NSString` borrows `buf`, so the compiler prevents `buf`
+    /// from being dropped while Core Foundation still references it.
+    pub fn from_utf16_no_copy(buf: &'a [u16]) -> NSString<'a> {
+        unsafe {
+            let cf_ref = ffi::CFStringCreateWithCharactersNoCopy(
+                ffi::kCFAllocatorDefault,
+                buf.as_ptr(),
+                buf.len() as ffi::CFIndex,
+                ffi::kCFAllocatorNull,
+            );
+            NSString::from_ptr(cf_ref)
+        }
+    }
+
+    /// Creates an `NSString` that wraps the UTF-8 bytes of `s` directly,
+    /// without copying or transcoding.
+    ///
+    /// See [`from_utf16_no_copy`](NSString::from_utf16_no_copy) for details
+    /// on how the no-copy behavior is achieved; this is the `&str`
+    /// counterpart for callers who already have UTF-8 data in memory.
+    pub fn from_str_no_copy(s: &'a str) -> NSString<'a> {
+        unsafe {
+            let cf_ref = ffi::CFStringCreateWithBytesNoCopy(
+                ffi::kCFAllocatorDefault,
+                s.as_ptr(),
+                s.len() as ffi::CFIndex,
+                ffi::kCFStringEncodingUTF8,
+                0,
+                ffi::kCFAllocatorNull,
+            );
+            NSString::from_ptr(cf_ref)
+        }
+    }
+
+    /// Borrows this string's contents as UTF-8 without copying, if the
+    /// string already stores a contiguous, NUL-terminated UTF-8 (or ASCII)
+    /// buffer internally.
+    ///
+    /// This succeeds for strings built by [`nsstring!`](crate::nsstring!)
+    /// via its ASCII fast path, and for many other `CFString`-backed
+    /// instances, but is not guaranteed to succeed in general: `CFString` is
+    /// free to store its backing buffer as UTF-16, in which case this
+    /// returns `None` and [`to_str`](NSString::to_str) should be used
+    /// instead.
+    ///
+    /// # Lifetime
+    ///
+    /// The returned `&str` borrows directly from the `CFString`'s internal
+    /// buffer and is only valid while `self` is alive. For
+    /// [`NSMutableString`](struct.NSMutableString.html), any subsequent
+    /// mutation invalidates it, so the borrow must not be held across a
+    /// mutating call.
+    pub fn as_str(&self) -> Option<&str> {
+        unsafe {
+            let ptr = ffi::CFStringGetCStringPtr(self.as_cf_ref(), ffi::kCFStringEncodingUTF8);
+            if ptr.is_null() {
+                return None;
+            }
+
+            // Neither `CFStringGetLength` (the UTF-16 *code-unit* count,
+            // which only matches the UTF-8 byte length for ASCII content)
+            // nor NUL-scanning the buffer (which stops early on the interior
+            // NULs `nsstring!` explicitly allows) gives the real byte
+            // length. Ask `CFStringGetBytes` how many bytes a full UTF-8
+            // conversion of the string takes; since `ptr` already points at
+            // that exact encoding, this is precisely its length.
+            let utf16_len = ffi::CFStringGetLength(self.as_cf_ref());
+            let mut used_buf_len: ffi::CFIndex = 0;
+            ffi::CFStringGetBytes(
+                self.as_cf_ref(),
+                ffi::CFRange {
+                    location: 0,
+                    length: utf16_len,
+                },
+                ffi::kCFStringEncodingUTF8,
+                0,
+                0,
+                std::ptr::null_mut(),
+                0,
+                &mut used_buf_len,
+            );
+
+            let bytes = std::slice::from_raw_parts(ptr as *const u8, used_buf_len as usize);
+
+            // Safe because `CFStringGetCStringPtr` with `kCFStringEncodingUTF8`
+            // only returns non-null when the backing buffer is already valid
+            // UTF-8 (the `nsstring!` ASCII path guarantees this, and ASCII is
+            // a subset of UTF-8).
+            Some(std::str::from_utf8_unchecked(bytes))
+        }
+    }
+
+    /// Returns this string's contents as UTF-8, borrowing via
+    /// [`as_str`](NSString::as_str) when possible and otherwise falling back
+    /// to an allocating UTF-16 transcode.
+    pub fn to_str(&self) -> Cow<'_, str> {
+        match self.as_str() {
+            Some(s) => Cow::Borrowed(s),
+            None => Cow::Owned(self.to_utf16_string()),
+        }
+    }
+
+    /// Allocates a new `String` by reading this string's contents out as
+    /// UTF-16 and transcoding to UTF-8.
+    fn to_utf16_string(&self) -> String {
+        unsafe {
+            let len = ffi::CFStringGetLength(self.as_cf_ref()) as usize;
+            let mut buf = vec![0u16; len];
+            ffi::CFStringGetCharacters(
+                self.as_cf_ref(),
+                ffi::CFRange {
+                    location: 0,
+                    length: len as ffi::CFIndex,
+                },
+                buf.as_mut_ptr(),
+            );
+            String::from_utf16_lossy(&buf)
+        }
+    }
+}
+
+impl fmt::Display for NSString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_str() {
+            Some(s) => f.write_str(s),
+            None => f.write_str(&self.to_utf16_string()),
+        }
+    }
+}
+
+impl fmt::Debug for NSString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_str(), f)
+    }
+}
+
+impl PartialEq for NSString<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_str() == other.to_str()
+    }
+}
+
+/// A mutable Unicode string, toll-free bridged with `CFMutableString`.
+///
+/// `NSMutableString` is an `NSString` subclass, so it dereferences to
+/// [`NSString`] for shared functionality.
+///
+/// # Feature Flag
+///
+/// This is defined in [`foundation`](index.html), which requires the
+/// **`foundation`** [feature flag](index.html#feature-flags).
+#[repr(transparent)]
+pub struct NSMutableString<'a> {
+    inner: NSString<'a>,
+}
+
+impl<'a> NSMutableString<'a> {
+    /// Wraps a raw, already-retained `NSMutableString *` (or toll-free
+    /// bridged `CFMutableStringRef`) pointer.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must refer to a valid `NSMutableString` instance that stays
+    /// alive for at least `'a`.
+    pub const unsafe fn from_ptr(ptr: *const c_void) -> Self {
+        NSMutableString {
+            inner: NSString::from_ptr(ptr),
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for NSMutableString<'a> {
+    type Target = NSString<'a>;
+
+    fn deref(&self) -> &NSString<'a> {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NSString;
+
+    #[test]
+    fn from_str_no_copy_round_trips_non_ascii() {
+        for s in ["héllo", "a🦀", "lööps, bröther?"] {
+            let nsstring = NSString::from_str_no_copy(s);
+
+            assert_eq!(nsstring.as_str(), Some(s));
+            assert_eq!(nsstring.to_str(), s);
+            assert_eq!(nsstring.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn from_utf16_no_copy_round_trips_surrogate_pair() {
+        for s in ["héllo", "a🦀crab", "lööps, bröther?"] {
+            let buf: Vec<u16> = s.encode_utf16().collect();
+            let nsstring = NSString::from_utf16_no_copy(&buf);
+
+            // The buffer is UTF-16, not UTF-8, so `CFStringGetCStringPtr`
+            // never has a UTF-8 buffer to hand back directly here.
+            assert_eq!(nsstring.as_str(), None);
+            assert_eq!(nsstring.to_str(), s);
+            assert_eq!(nsstring.to_string(), s);
+        }
+    }
+}
@@ -38,7 +38,19 @@
 /// In Objective-C, non-ASCII strings are UTF-16. However, Rust strings are
 /// UTF-8.
 ///
-/// This macro transcodes non-ASCII strings to UTF-16:
+/// This macro transcodes non-ASCII strings to UTF-16, unless every scalar in
+/// the string fits in a single Latin-1 (ISO-8859-1) byte, in which case it
+/// is stored in that smaller 8-bit representation instead:
+///
+/// ```
+/// # use fruity::foundation::NSString;
+/// static LOOPS: NSString = fruity::nsstring!("lööps, bröther?");
+///
+/// assert_eq!(LOOPS.to_string(), "lööps, bröther?");
+/// ```
+///
+/// Any string containing a scalar above `U+00FF` still falls back to the
+/// full UTF-16 representation:
 ///
 /// ```
 /// # use fruity::foundation::NSString;
@@ -65,8 +77,8 @@
 /// Interior null bytes are allowed and are not stripped:
 ///
 /// ```
-/// # // TODO: Add `to_string()` test when a Rust strings with nulls can be retrieved.
 /// let example = fruity::nsstring!("exa\0mple");
+/// assert_eq!(example.to_string(), "exa\0mple");
 /// ```
 ///
 /// # Runtime Cost
@@ -135,53 +147,125 @@ macro_rules! nsstring {
 
                 CFSTRING.as_ptr()
             } else {
-                // The full UTF-16 contents along with the written length.
-                const UTF16_FULL: (&[u16; INPUT.len()], usize) = {
-                    let mut out = [0u16; INPUT.len()];
+                // Every scalar fits in 8 bits (Latin-1/ISO-8859-1) when every
+                // encoded UTF-16 code unit does: such a scalar never needs a
+                // surrogate pair, and its single code unit equals the scalar
+                // value.
+                const IS_LATIN1: bool = {
                     let mut iter = $crate::_priv::cfstring::utf16::EncodeUtf16Iter::new(INPUT);
-                    let mut written = 0;
+                    let mut latin1 = true;
 
                     while let Some((state, chars)) = iter.next() {
                         iter = state;
-                        out[written] = chars.repr[0];
-                        written += 1;
 
-                        if chars.len > 1 {
-                            out[written] = chars.repr[1];
-                            written += 1;
+                        if chars.len > 1 || chars.repr[0] > 0xFF {
+                            latin1 = false;
                         }
                     }
 
-                    (&{ out }, written)
+                    latin1
                 };
 
-                // The written UTF-16 contents with a trailing null code point.
-                #[repr(C)]
-                struct Utf16 {
-                    data: [u16; UTF16_FULL.1],
-                    nul: u16,
-                }
+                if IS_LATIN1 {
+                    // The Latin-1 code units along with the written length.
+                    const LATIN1_FULL: (&[u8; INPUT.len()], usize) = {
+                        let mut out = [0u8; INPUT.len()];
+                        let mut iter = $crate::_priv::cfstring::utf16::EncodeUtf16Iter::new(INPUT);
+                        let mut written = 0;
 
-                const UTF16: Utf16 = Utf16 {
-                    data: unsafe {
-                        *$crate::_priv::std::mem::transmute::<_, &_>(UTF16_FULL.0.as_ptr())
-                    },
-                    nul: 0,
-                };
+                        while let Some((state, chars)) = iter.next() {
+                            iter = state;
+                            out[written] = chars.repr[0] as u8;
+                            written += 1;
+                        }
 
-                const UTF16_ARRAY: &[u16; UTF16_FULL.1 + 1] =
-                    unsafe { $crate::_priv::std::mem::transmute(&UTF16) };
+                        (&{ out }, written)
+                    };
 
-                #[link_section = "__DATA,__cfstring,regular"]
-                static CFSTRING: $crate::_priv::cfstring::CFStringUtf16 =
-                    $crate::_priv::cfstring::CFStringUtf16::new(
-                        unsafe { &__CFConstantStringClassReference },
-                        UTF16_ARRAY.as_ptr(),
-                        // The length does not include the trailing null.
-                        UTF16_FULL.1,
-                    );
+                    // The Latin-1 bytes with a trailing null byte, using the
+                    // same 8-bit `CFStringAscii` record layout as the ASCII
+                    // branch above: CF's "8-bit" constant-string storage is
+                    // not restricted to 7-bit ASCII, it is read back via
+                    // `CFStringGetCStringPtr(_, kCFStringEncodingISOLatin1)`
+                    // for the high-bit-set bytes produced here. The
+                    // `latin1_fast_path_uses_iso_latin1_string` test below
+                    // exercises that exact call to confirm it, rather than
+                    // relying only on this comment.
+                    #[repr(C)]
+                    struct Latin1 {
+                        data: [u8; LATIN1_FULL.1],
+                        nul: u8,
+                    }
 
-                CFSTRING.as_ptr()
+                    const LATIN1: Latin1 = Latin1 {
+                        data: unsafe {
+                            *$crate::_priv::std::mem::transmute::<_, &_>(LATIN1_FULL.0.as_ptr())
+                        },
+                        nul: 0,
+                    };
+
+                    const LATIN1_ARRAY: &[u8; LATIN1_FULL.1 + 1] =
+                        unsafe { $crate::_priv::std::mem::transmute(&LATIN1) };
+
+                    #[link_section = "__DATA,__cfstring,regular"]
+                    static CFSTRING: $crate::_priv::cfstring::CFStringAscii =
+                        $crate::_priv::cfstring::CFStringAscii::new(
+                            unsafe { &__CFConstantStringClassReference },
+                            LATIN1_ARRAY.as_ptr(),
+                            // The length does not include the trailing null.
+                            LATIN1_FULL.1,
+                        );
+
+                    CFSTRING.as_ptr()
+                } else {
+                    // The full UTF-16 contents along with the written length.
+                    const UTF16_FULL: (&[u16; INPUT.len()], usize) = {
+                        let mut out = [0u16; INPUT.len()];
+                        let mut iter = $crate::_priv::cfstring::utf16::EncodeUtf16Iter::new(INPUT);
+                        let mut written = 0;
+
+                        while let Some((state, chars)) = iter.next() {
+                            iter = state;
+                            out[written] = chars.repr[0];
+                            written += 1;
+
+                            if chars.len > 1 {
+                                out[written] = chars.repr[1];
+                                written += 1;
+                            }
+                        }
+
+                        (&{ out }, written)
+                    };
+
+                    // The written UTF-16 contents with a trailing null code point.
+                    #[repr(C)]
+                    struct Utf16 {
+                        data: [u16; UTF16_FULL.1],
+                        nul: u16,
+                    }
+
+                    const UTF16: Utf16 = Utf16 {
+                        data: unsafe {
+                            *$crate::_priv::std::mem::transmute::<_, &_>(UTF16_FULL.0.as_ptr())
+                        },
+                        nul: 0,
+                    };
+
+                    const UTF16_ARRAY: &[u16; UTF16_FULL.1 + 1] =
+                        unsafe { $crate::_priv::std::mem::transmute(&UTF16) };
+
+                    #[link_section = "__DATA,__cfstring,regular"]
+                    static CFSTRING: $crate::_priv::cfstring::CFStringUtf16 =
+                        $crate::_priv::cfstring::CFStringUtf16::new(
+                            unsafe { &__CFConstantStringClassReference },
+                            UTF16_ARRAY.as_ptr(),
+                            // The length does not include the trailing null.
+                            UTF16_FULL.1,
+                        );
+
+                    CFSTRING.as_ptr()
+                }
             }
         };
 
@@ -194,7 +278,7 @@ macro_rules! nsstring {
 
 #[cfg(test)]
 mod tests {
-    use super::super::NSString;
+    use super::super::super::NSString;
 
     #[test]
     fn nsstring() {
@@ -218,4 +302,61 @@ mod tests {
             "讓每個人都能打造出。",
         }
     }
+
+    #[test]
+    fn interior_nul_is_preserved() {
+        static STRING: NSString = nsstring!("exa\0mple");
+
+        // ASCII (including the NUL byte itself) takes the 8-bit fast path,
+        // so this also exercises `as_str()`'s ability to return the full
+        // contents rather than truncating at the first NUL.
+        assert_eq!(STRING.as_str(), Some("exa\0mple"));
+        assert_eq!(STRING.to_string(), "exa\0mple");
+    }
+
+    #[test]
+    fn latin1() {
+        macro_rules! test {
+            ($($s:expr,)+) => {$({
+                static STRING: NSString = nsstring!($s);
+                assert_eq!(STRING.to_string(), $s);
+            })+};
+        }
+
+        // None of these are pure ASCII, but every scalar fits in a single
+        // Latin-1 byte, so these exercise the 8-bit fast path rather than
+        // the full UTF-16 one.
+        test! {
+            "ä",
+            "ääääh",
+            "lööps, bröther?",
+            "Äpfel, Öl, über",
+            "café",
+        }
+    }
+
+    #[test]
+    fn latin1_fast_path_uses_iso_latin1_string() {
+        use super::super::super::ffi;
+
+        static STRING: NSString = nsstring!("lööps, bröther?");
+
+        // Force the exact fast path the Latin-1 branch is meant to enable:
+        // reading the 8-bit record back as ISO-8859-1, without CF needing to
+        // transcode it to UTF-16 first.
+        let ptr = unsafe {
+            ffi::CFStringGetCStringPtr(
+                STRING.as_cf_ref(),
+                ffi::kCFStringEncodingISOLatin1,
+            )
+        };
+        assert!(
+            !ptr.is_null(),
+            "expected the Latin-1 8-bit record to be readable as ISO-8859-1"
+        );
+
+        let bytes = unsafe { std::ffi::CStr::from_ptr(ptr) }.to_bytes();
+        let decoded: String = bytes.iter().map(|&b| b as char).collect();
+        assert_eq!(decoded, "lööps, bröther?");
+    }
 }